@@ -0,0 +1,85 @@
+/// A tape cell type. Implemented for `u8`, `u16`, and `u32` so `Config::cell_width`
+/// can pick the interpreter's integer width at runtime while the hot loop in
+/// `interpreter` stays monomorphic over a single concrete `Cell`.
+pub trait Cell: Copy + Default + PartialEq {
+    /// Adds `delta` to `self`, wrapping to this cell's own width. `delta` is kept
+    /// wide (not sized to match `Self`) because folded `Add`/`MulAdd` counts can
+    /// exceed this cell's range even when the final, wrapped result fits.
+    fn wrapping_add_signed(self, delta: i32) -> Self;
+    fn wrapping_add(self, other: Self) -> Self;
+    /// Multiplies by a signed factor, truncating to the cell width the same way
+    /// the underlying unsigned arithmetic wraps.
+    fn wrapping_mul_small(self, factor: i32) -> Self;
+    /// Widens a single input byte into this cell's width.
+    fn from_input_byte(byte: u8) -> Self;
+    /// Truncates this cell down to the single byte `.` writes.
+    fn to_output_byte(self) -> u8;
+    /// The cell type's maximum value, used for the EOF=-1 dialect.
+    fn all_ones() -> Self;
+}
+
+macro_rules! impl_cell {
+    ($ty:ty) => {
+        impl Cell for $ty {
+            fn wrapping_add_signed(self, delta: i32) -> Self {
+                ((self as i128) + (delta as i128)) as $ty
+            }
+            fn wrapping_add(self, other: Self) -> Self {
+                <$ty>::wrapping_add(self, other)
+            }
+            fn wrapping_mul_small(self, factor: i32) -> Self {
+                ((self as i128) * (factor as i128)) as $ty
+            }
+            fn from_input_byte(byte: u8) -> Self {
+                byte as $ty
+            }
+            fn to_output_byte(self) -> u8 {
+                self as u8
+            }
+            fn all_ones() -> Self {
+                <$ty>::MAX
+            }
+        }
+    };
+}
+
+impl_cell!(u8);
+impl_cell!(u16);
+impl_cell!(u32);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrapping_add_signed() {
+        assert_eq!(Cell::wrapping_add_signed(250u8, 10), 4);
+        assert_eq!(Cell::wrapping_add_signed(0u16, -1), u16::MAX);
+    }
+
+    #[test]
+    fn test_wrapping_add_signed_beyond_i8_range() {
+        // A folded run of 300 '+' must land on the right value for a wide cell,
+        // not get truncated to i8 range first.
+        assert_eq!(Cell::wrapping_add_signed(0u16, 300), 300u16);
+        assert_eq!(Cell::wrapping_add_signed(0u8, 300), 300u16 as u8); // wraps mod 256 for u8
+    }
+
+    #[test]
+    fn test_wrapping_mul_small() {
+        assert_eq!(Cell::wrapping_mul_small(100u8, 3), 44); // 300 % 256
+        assert_eq!(Cell::wrapping_mul_small(10u32, -2), u32::MAX - 19);
+    }
+
+    #[test]
+    fn test_input_output_roundtrip() {
+        assert_eq!(u16::from_input_byte(200), 200u16);
+        assert_eq!(Cell::to_output_byte(300u16), 300u16 as u8);
+    }
+
+    #[test]
+    fn test_all_ones() {
+        assert_eq!(u8::all_ones(), 255);
+        assert_eq!(u32::all_ones(), u32::MAX);
+    }
+}
@@ -0,0 +1,67 @@
+/// Which integer width backs each tape cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellWidth {
+    U8,
+    U16,
+    U32,
+}
+
+/// How the tape behaves at its boundaries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TapeMode {
+    /// The tape starts at cell 0 and only grows to the right; moving left of cell 0
+    /// is a `PointerUnderflow` error. This is the classic dialect's behavior.
+    GrowRight,
+    /// The tape grows in both directions as the pointer moves past either end.
+    Bidirectional,
+    /// The tape is a fixed-size ring of `initial_tape_size` cells; the pointer
+    /// wraps around at either end instead of growing or erroring.
+    Wrapping,
+}
+
+/// What `,` stores in the current cell once the input stream is exhausted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EofBehavior {
+    /// Leave the current cell's value untouched.
+    Unchanged,
+    /// Write zero.
+    Zero,
+    /// Write the cell type's maximum value (the EOF=-1 dialect).
+    AllOnes,
+}
+
+/// Dialect settings threaded through `run`/`run_bytecode`. Different brainfuck
+/// programs assume different cell widths, tape behaviors, and EOF conventions;
+/// `Config` lets callers pick the dialect instead of hardcoding one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    pub cell_width: CellWidth,
+    pub initial_tape_size: usize,
+    pub tape_mode: TapeMode,
+    pub eof_behavior: EofBehavior,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            cell_width: CellWidth::U8,
+            initial_tape_size: 1024,
+            tape_mode: TapeMode::GrowRight,
+            eof_behavior: EofBehavior::Unchanged,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_matches_classic_dialect() {
+        let config = Config::default();
+        assert_eq!(config.cell_width, CellWidth::U8);
+        assert_eq!(config.initial_tape_size, 1024);
+        assert_eq!(config.tape_mode, TapeMode::GrowRight);
+        assert_eq!(config.eof_behavior, EofBehavior::Unchanged);
+    }
+}
@@ -0,0 +1,43 @@
+use std::fmt;
+
+/// Errors produced while parsing or running a brainfuck program.
+#[derive(Debug)]
+pub enum BfError {
+    /// A `]` with no matching `[`, at the given character index in the source.
+    UnmatchedClose { pos: usize },
+    /// A `[` with no matching `]`, at the given character index in the source.
+    UnmatchedOpen { pos: usize },
+    /// The pointer tried to move left of cell 0.
+    PointerUnderflow,
+    /// An I/O error occurred while reading `,` or writing `.`.
+    Io(std::io::Error),
+    /// An `Abort` instruction was executed.
+    Aborted,
+}
+
+impl fmt::Display for BfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BfError::UnmatchedClose { pos } => write!(f, "unmatched ']' at column {pos}"),
+            BfError::UnmatchedOpen { pos } => write!(f, "unmatched '[' at column {pos}"),
+            BfError::PointerUnderflow => write!(f, "pointer moved left of cell 0"),
+            BfError::Io(err) => write!(f, "I/O error: {err}"),
+            BfError::Aborted => write!(f, "program aborted"),
+        }
+    }
+}
+
+impl std::error::Error for BfError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BfError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for BfError {
+    fn from(err: std::io::Error) -> Self {
+        BfError::Io(err)
+    }
+}
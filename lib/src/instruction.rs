@@ -9,4 +9,16 @@ pub enum Instruction {
     BeginLoop(usize),
     EndLoop(usize),
     Abort,
+    /// Add `n` to the current cell in one step (wrapping), folded from a run of `+`/`-`.
+    /// Kept wide (not `i8`) so a run longer than 127 chars doesn't truncate before it
+    /// ever reaches a cell — the cell type's own width is what should wrap it.
+    Add(i32),
+    /// Move the pointer by `n` cells in one step, folded from a run of `>`/`<`.
+    Move(isize),
+    /// Set the current cell to zero, folded from a `[-]`/`[+]`-style clear loop.
+    SetZero,
+    /// Add `factor` times the current cell to the cell at `offset`, folded from a
+    /// `[- Move(offset) Add(factor) Move(-offset)]`-style multiply-add loop. Does not
+    /// itself clear the current cell; callers must pair it with a following `SetZero`.
+    MulAdd(isize, i32),
 }
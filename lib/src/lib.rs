@@ -1,11 +1,25 @@
+use crate::config::{CellWidth, Config};
+use crate::error::BfError;
 use crate::interpreter::run_bytecode;
-use std::io::Stdin;
 
+pub mod cell;
+pub mod config;
+pub mod error;
 pub mod instruction;
 pub mod interpreter;
+pub mod optimize;
 pub mod parse;
+pub mod transpile;
 
-pub fn run(code: &str) {
-    let bytecode = parse::parse(code);
-    run_bytecode(&bytecode, std::io::stdin(), std::io::stdout());
+pub fn run(code: &str, config: &Config) -> Result<(), BfError> {
+    let bytecode = optimize::optimize(parse::parse(code)?);
+    match config.cell_width {
+        CellWidth::U8 => run_bytecode::<u8>(&bytecode, std::io::stdin(), std::io::stdout(), config),
+        CellWidth::U16 => {
+            run_bytecode::<u16>(&bytecode, std::io::stdin(), std::io::stdout(), config)
+        }
+        CellWidth::U32 => {
+            run_bytecode::<u32>(&bytecode, std::io::stdin(), std::io::stdout(), config)
+        }
+    }
 }
@@ -0,0 +1,198 @@
+use crate::instruction::Instruction;
+
+/// Rewrites a flat, one-instruction-per-character bytecode into a denser form:
+/// runs of `+`/`-` and `>`/`<` are folded into single `Add`/`Move` steps, and
+/// balanced loops that only clear or multiply-add a cell are collapsed into
+/// `SetZero`/`MulAdd`. Loops the optimizer cannot prove are balanced and
+/// pointer-neutral are left untouched.
+pub fn optimize(bytecode: Vec<Instruction>) -> Vec<Instruction> {
+    let mut bytecode = fold_runs(bytecode);
+    bytecode = collapse_loops(bytecode);
+    recompute_loop_targets(&mut bytecode);
+    bytecode
+}
+
+fn fold_runs(bytecode: Vec<Instruction>) -> Vec<Instruction> {
+    let mut output: Vec<Instruction> = Vec::with_capacity(bytecode.len());
+
+    for instruction in bytecode {
+        match (output.last_mut(), instruction) {
+            (Some(Instruction::Add(n)), Instruction::Increment) => *n = n.wrapping_add(1),
+            (Some(Instruction::Add(n)), Instruction::Decrement) => *n = n.wrapping_sub(1),
+            (Some(Instruction::Move(d)), Instruction::GoRight) => *d += 1,
+            (Some(Instruction::Move(d)), Instruction::GoLeft) => *d -= 1,
+            _ => output.push(match instruction {
+                Instruction::Increment => Instruction::Add(1),
+                Instruction::Decrement => Instruction::Add(-1),
+                Instruction::GoRight => Instruction::Move(1),
+                Instruction::GoLeft => Instruction::Move(-1),
+                other => other,
+            }),
+        }
+    }
+
+    output.retain(|instruction| !matches!(instruction, Instruction::Add(0) | Instruction::Move(0)));
+    output
+}
+
+/// Collapses `[Add(n)]` (`n` odd, so the cell reaches zero) into `SetZero`, and
+/// `[Add(-1) Move(d) Add(k) Move(-d)]` into `MulAdd(d, k)` followed by `SetZero`.
+/// Runs a single left-to-right pass so inner loops are already collapsed by the
+/// time their enclosing loop's body is inspected. `BeginLoop`/`EndLoop` targets
+/// are left as placeholders; `recompute_loop_targets` fixes them up afterwards.
+fn collapse_loops(bytecode: Vec<Instruction>) -> Vec<Instruction> {
+    let mut output: Vec<Instruction> = Vec::with_capacity(bytecode.len());
+    let mut loop_starts: Vec<usize> = Vec::new();
+
+    for instruction in bytecode {
+        match instruction {
+            Instruction::BeginLoop(_) => {
+                loop_starts.push(output.len());
+                output.push(Instruction::BeginLoop(0));
+            }
+            Instruction::EndLoop(_) => {
+                let start = loop_starts.pop().expect("parser guarantees balanced loops");
+                let body = &output[start + 1..];
+
+                if let Some(replacement) = simplify_loop_body(body) {
+                    output.truncate(start);
+                    output.extend(replacement);
+                } else {
+                    output.push(Instruction::EndLoop(0));
+                }
+            }
+            other => output.push(other),
+        }
+    }
+
+    output
+}
+
+fn simplify_loop_body(body: &[Instruction]) -> Option<Vec<Instruction>> {
+    match body {
+        [Instruction::Add(n)] if n % 2 != 0 => Some(vec![Instruction::SetZero]),
+        [Instruction::Add(-1), Instruction::Move(d1), Instruction::Add(k), Instruction::Move(d2)]
+            if *d1 == -*d2 =>
+        {
+            Some(vec![Instruction::MulAdd(*d1, *k), Instruction::SetZero])
+        }
+        _ => None,
+    }
+}
+
+fn recompute_loop_targets(bytecode: &mut [Instruction]) {
+    let mut loop_starts: Vec<usize> = Vec::new();
+
+    for idx in 0..bytecode.len() {
+        match bytecode[idx] {
+            Instruction::BeginLoop(_) => loop_starts.push(idx),
+            Instruction::EndLoop(_) => {
+                let start = loop_starts.pop().expect("parser guarantees balanced loops");
+                bytecode[start] = Instruction::BeginLoop(idx);
+                bytecode[idx] = Instruction::EndLoop(start);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction::Instruction::*;
+
+    #[test]
+    fn test_folds_increment_run() {
+        let result = optimize(vec![Increment, Increment, Increment]);
+        assert_eq!(result, vec![Add(3)]);
+    }
+
+    #[test]
+    fn test_folds_mixed_increment_decrement_run() {
+        let result = optimize(vec![Increment, Increment, Decrement, Increment]);
+        assert_eq!(result, vec![Add(2)]);
+    }
+
+    #[test]
+    fn test_folds_move_run() {
+        let result = optimize(vec![GoRight, GoRight, GoLeft]);
+        assert_eq!(result, vec![Move(1)]);
+    }
+
+    #[test]
+    fn test_drops_net_zero_moves_and_adds() {
+        let result = optimize(vec![GoRight, GoLeft, Increment, Decrement, Output]);
+        assert_eq!(result, vec![Output]);
+    }
+
+    #[test]
+    fn test_does_not_fold_across_other_instructions() {
+        let result = optimize(vec![Increment, Output, Increment]);
+        assert_eq!(result, vec![Add(1), Output, Add(1)]);
+    }
+
+    #[test]
+    fn test_collapses_clear_loop() {
+        let result = optimize(vec![BeginLoop(2), Decrement, EndLoop(0)]);
+        assert_eq!(result, vec![SetZero]);
+    }
+
+    #[test]
+    fn test_leaves_even_loop_body_untouched() {
+        let result = optimize(vec![BeginLoop(3), Decrement, Decrement, EndLoop(0)]);
+        assert_eq!(result, vec![BeginLoop(2), Add(-2), EndLoop(0)]);
+    }
+
+    #[test]
+    fn test_collapses_multiply_add_loop() {
+        // [->+++<]
+        let result = optimize(vec![
+            BeginLoop(7),
+            Decrement,
+            GoRight,
+            Increment,
+            Increment,
+            Increment,
+            GoLeft,
+            EndLoop(0),
+        ]);
+        assert_eq!(result, vec![MulAdd(1, 3), SetZero]);
+    }
+
+    #[test]
+    fn test_leaves_pointer_unbalanced_loop_untouched() {
+        // [->+<<] nets to a pointer move of -1, so it must not collapse.
+        let result = optimize(vec![
+            BeginLoop(7),
+            Decrement,
+            GoRight,
+            Increment,
+            GoLeft,
+            GoLeft,
+            EndLoop(0),
+        ]);
+        assert_eq!(
+            result,
+            vec![BeginLoop(5), Add(-1), Move(1), Add(1), Move(-2), EndLoop(0)]
+        );
+    }
+
+    #[test]
+    fn test_recomputes_nested_loop_targets_after_collapse() {
+        // [>[-]<+]
+        let result = optimize(vec![
+            BeginLoop(7),
+            GoRight,
+            BeginLoop(4),
+            Decrement,
+            EndLoop(2),
+            GoLeft,
+            Increment,
+            EndLoop(0),
+        ]);
+        assert_eq!(
+            result,
+            vec![BeginLoop(5), Move(1), SetZero, Move(-1), Add(1), EndLoop(0)]
+        );
+    }
+}
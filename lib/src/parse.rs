@@ -1,10 +1,11 @@
+use crate::error::BfError;
 use crate::instruction::Instruction;
 use crate::instruction::Instruction::{
     Abort, BeginLoop, Decrement, EndLoop, GoLeft, GoRight, Increment, Input, Output,
 };
 
-pub fn parse(code: &str) -> Vec<Instruction> {
-    let mut code = code.chars().enumerate();
+pub fn parse(code: &str) -> Result<Vec<Instruction>, BfError> {
+    let code = code.chars().enumerate();
 
     let mut output = Vec::new();
     let mut loop_stack: Vec<usize> = Vec::new();
@@ -22,7 +23,9 @@ pub fn parse(code: &str) -> Vec<Instruction> {
                 Abort
             }
             ']' => {
-                let open_idx = loop_stack.pop().unwrap();
+                let open_idx = loop_stack
+                    .pop()
+                    .ok_or(BfError::UnmatchedClose { pos: idx })?;
                 output[open_idx] = BeginLoop(idx);
                 EndLoop(open_idx)
             }
@@ -34,7 +37,11 @@ pub fn parse(code: &str) -> Vec<Instruction> {
         output.push(new_instruction);
     }
 
-    output
+    if let Some(&pos) = loop_stack.first() {
+        return Err(BfError::UnmatchedOpen { pos });
+    }
+
+    Ok(output)
 }
 
 #[cfg(test)]
@@ -43,7 +50,7 @@ mod tests {
 
     #[test]
     fn test_basic_instructions() {
-        let result = parse("+-<>.,");
+        let result = parse("+-<>.,").unwrap();
         assert_eq!(
             result,
             vec![Increment, Decrement, GoLeft, GoRight, Output, Input,]
@@ -52,13 +59,13 @@ mod tests {
 
     #[test]
     fn test_simple_loop() {
-        let result = parse("[+]");
+        let result = parse("[+]").unwrap();
         assert_eq!(result, vec![BeginLoop(2), Increment, EndLoop(0),]);
     }
 
     #[test]
     fn test_nested_loops() {
-        let result = parse("[[+]]");
+        let result = parse("[[+]]").unwrap();
         assert_eq!(
             result,
             vec![
@@ -73,7 +80,7 @@ mod tests {
 
     #[test]
     fn test_complex_program() {
-        let result = parse("+[>+<-]");
+        let result = parse("+[>+<-]").unwrap();
         assert_eq!(
             result,
             vec![
@@ -90,25 +97,25 @@ mod tests {
 
     #[test]
     fn test_ignore_non_brainfuck_chars() {
-        let result = parse("+ hello world -");
+        let result = parse("+ hello world -").unwrap();
         assert_eq!(result, vec![Increment, Decrement,]);
     }
 
     #[test]
     fn test_empty_string() {
-        let result = parse("");
+        let result = parse("").unwrap();
         assert_eq!(result, vec![]);
     }
 
     #[test]
     fn test_only_comments() {
-        let result = parse("this is a comment");
+        let result = parse("this is a comment").unwrap();
         assert_eq!(result, vec![]);
     }
 
     #[test]
     fn test_multiple_loops() {
-        let result = parse("[+][>]");
+        let result = parse("[+][>]").unwrap();
         assert_eq!(
             result,
             vec![
@@ -124,13 +131,13 @@ mod tests {
 
     #[test]
     fn test_empty_loop() {
-        let result = parse("[]");
+        let result = parse("[]").unwrap();
         assert_eq!(result, vec![BeginLoop(1), EndLoop(0),]);
     }
 
     #[test]
     fn test_deeply_nested_loops() {
-        let result = parse("[[[+]]]");
+        let result = parse("[[[+]]]").unwrap();
         assert_eq!(
             result,
             vec![
@@ -144,4 +151,22 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_unmatched_close() {
+        let result = parse("+]");
+        assert!(matches!(result, Err(BfError::UnmatchedClose { pos: 1 })));
+    }
+
+    #[test]
+    fn test_unmatched_open() {
+        let result = parse("[+");
+        assert!(matches!(result, Err(BfError::UnmatchedOpen { pos: 0 })));
+    }
+
+    #[test]
+    fn test_unmatched_open_reports_outermost() {
+        let result = parse("[[+]");
+        assert!(matches!(result, Err(BfError::UnmatchedOpen { pos: 0 })));
+    }
 }
@@ -0,0 +1,319 @@
+use crate::instruction::Instruction;
+
+/// Which language `transpile` emits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Rust,
+    C,
+}
+
+/// Emits a self-contained program implementing the same tape/pointer semantics as
+/// `interpreter::run_bytecode` (a `u8` tape that grows to the right, EOF leaves
+/// the cell unchanged): loops map to `while (mem[p]) { ... }` blocks using the
+/// balanced structure already captured by `BeginLoop`/`EndLoop`, and folded
+/// `Add`/`Move`/`SetZero`/`MulAdd` instructions (see `optimize`) emit compact,
+/// direct tape operations instead of one statement per original character. This
+/// gives an ahead-of-time path to a native executable for compute-heavy programs.
+pub fn transpile(bytecode: &[Instruction], target: Target) -> String {
+    match target {
+        Target::Rust => transpile_rust(bytecode),
+        Target::C => transpile_c(bytecode),
+    }
+}
+
+fn push_line(body: &mut String, indent: usize, line: &str) {
+    body.push_str(&"    ".repeat(indent));
+    body.push_str(line);
+    body.push('\n');
+}
+
+fn transpile_rust(bytecode: &[Instruction]) -> String {
+    let mut body = String::new();
+    let mut indent = 1;
+
+    for instruction in bytecode {
+        match *instruction {
+            Instruction::GoRight => push_line(&mut body, indent, "mv(&mut mem, &mut p, 1);"),
+            Instruction::GoLeft => push_line(&mut body, indent, "mv(&mut mem, &mut p, -1);"),
+            Instruction::Increment => {
+                push_line(&mut body, indent, "mem[p] = mem[p].wrapping_add(1);")
+            }
+            Instruction::Decrement => {
+                push_line(&mut body, indent, "mem[p] = mem[p].wrapping_sub(1);")
+            }
+            Instruction::Output => {
+                push_line(&mut body, indent, "stdout.write_all(&[mem[p]]).unwrap();")
+            }
+            Instruction::Input => {
+                push_line(&mut body, indent, "let mut buf = [0u8; 1];");
+                push_line(
+                    &mut body,
+                    indent,
+                    "if stdin.read_exact(&mut buf).is_ok() { mem[p] = buf[0]; }",
+                );
+            }
+            Instruction::BeginLoop(_) => {
+                push_line(&mut body, indent, "while mem[p] != 0 {");
+                indent += 1;
+            }
+            Instruction::EndLoop(_) => {
+                indent -= 1;
+                push_line(&mut body, indent, "}");
+            }
+            Instruction::Abort => unreachable!("parser guarantees every '[' is matched"),
+            Instruction::Add(n) => push_line(
+                &mut body,
+                indent,
+                &format!("mem[p] = mem[p].wrapping_add({n}i32 as u8);"),
+            ),
+            Instruction::Move(d) => {
+                push_line(&mut body, indent, &format!("mv(&mut mem, &mut p, {d});"))
+            }
+            Instruction::SetZero => push_line(&mut body, indent, "mem[p] = 0;"),
+            Instruction::MulAdd(offset, factor) => {
+                push_line(
+                    &mut body,
+                    indent,
+                    &format!("let c = (mem[p] as i32).wrapping_mul({factor}) as u8;"),
+                );
+                push_line(&mut body, indent, &format!("mv(&mut mem, &mut p, {offset});"));
+                push_line(&mut body, indent, "mem[p] = mem[p].wrapping_add(c);");
+                push_line(
+                    &mut body,
+                    indent,
+                    &format!("mv(&mut mem, &mut p, {});", -offset),
+                );
+            }
+        }
+    }
+
+    format!(
+        "use std::io::{{Read, Write}};\n\
+         \n\
+         fn mv(mem: &mut Vec<u8>, p: &mut usize, delta: isize) {{\n\
+         \x20   if delta >= 0 {{\n\
+         \x20       for _ in 0..delta {{\n\
+         \x20           *p += 1;\n\
+         \x20           if *p == mem.len() {{\n\
+         \x20               mem.push(0);\n\
+         \x20           }}\n\
+         \x20       }}\n\
+         \x20   }} else {{\n\
+         \x20       for _ in 0..delta.unsigned_abs() {{\n\
+         \x20           *p -= 1;\n\
+         \x20       }}\n\
+         \x20   }}\n\
+         }}\n\
+         \n\
+         fn main() {{\n\
+         \x20   let mut mem: Vec<u8> = vec![0u8; 1024];\n\
+         \x20   let mut p: usize = 0;\n\
+         \x20   let stdin = std::io::stdin();\n\
+         \x20   let mut stdin = stdin.lock();\n\
+         \x20   let stdout = std::io::stdout();\n\
+         \x20   let mut stdout = stdout.lock();\n\
+         \n\
+         {body}\
+         }}\n"
+    )
+}
+
+fn transpile_c(bytecode: &[Instruction]) -> String {
+    let mut body = String::new();
+    let mut indent = 1;
+
+    for instruction in bytecode {
+        match *instruction {
+            Instruction::GoRight => push_line(&mut body, indent, "mv(1);"),
+            Instruction::GoLeft => push_line(&mut body, indent, "mv(-1);"),
+            Instruction::Increment => push_line(&mut body, indent, "mem[p]++;"),
+            Instruction::Decrement => push_line(&mut body, indent, "mem[p]--;"),
+            Instruction::Output => push_line(&mut body, indent, "putchar(mem[p]);"),
+            Instruction::Input => push_line(
+                &mut body,
+                indent,
+                "{ int ch = getchar(); if (ch != EOF) { mem[p] = (unsigned char)ch; } }",
+            ),
+            Instruction::BeginLoop(_) => {
+                push_line(&mut body, indent, "while (mem[p]) {");
+                indent += 1;
+            }
+            Instruction::EndLoop(_) => {
+                indent -= 1;
+                push_line(&mut body, indent, "}");
+            }
+            Instruction::Abort => unreachable!("parser guarantees every '[' is matched"),
+            Instruction::Add(n) => push_line(&mut body, indent, &format!("mem[p] += {n};")),
+            Instruction::Move(d) => push_line(&mut body, indent, &format!("mv({d});")),
+            Instruction::SetZero => push_line(&mut body, indent, "mem[p] = 0;"),
+            Instruction::MulAdd(offset, factor) => {
+                push_line(
+                    &mut body,
+                    indent,
+                    &format!(
+                        "{{ unsigned char c = (unsigned char)((int)mem[p] * {factor}); mv({offset}); mem[p] += c; mv({}); }}",
+                        -offset
+                    ),
+                );
+            }
+        }
+    }
+
+    format!(
+        "#include <stdio.h>\n\
+         #include <stdlib.h>\n\
+         #include <string.h>\n\
+         \n\
+         static unsigned char *mem;\n\
+         static size_t mem_len;\n\
+         static size_t p;\n\
+         \n\
+         static void mv(long delta) {{\n\
+         \x20   if (delta >= 0) {{\n\
+         \x20       for (long i = 0; i < delta; i++) {{\n\
+         \x20           p += 1;\n\
+         \x20           if (p == mem_len) {{\n\
+         \x20               mem = realloc(mem, mem_len * 2);\n\
+         \x20               memset(mem + mem_len, 0, mem_len);\n\
+         \x20               mem_len *= 2;\n\
+         \x20           }}\n\
+         \x20       }}\n\
+         \x20   }} else {{\n\
+         \x20       for (long i = 0; i < -delta; i++) {{\n\
+         \x20           p -= 1;\n\
+         \x20       }}\n\
+         \x20   }}\n\
+         }}\n\
+         \n\
+         int main(void) {{\n\
+         \x20   mem_len = 1024;\n\
+         \x20   mem = calloc(mem_len, 1);\n\
+         \x20   p = 0;\n\
+         \n\
+         {body}\
+         \x20   return 0;\n\
+         }}\n"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction::Instruction::*;
+
+    #[test]
+    fn test_rust_wide_add_run_compiles_and_runs() {
+        use std::process::Command;
+
+        // `optimize` folds a 200-character '+' run into Add(200); the emitted
+        // Rust must wrap that i32 into the cell's u8 width rather than pass it
+        // straight to `wrapping_add_signed`, which only accepts i8 and would
+        // make rustc reject the generated source outright.
+        let source = transpile(&[Add(200), Output], Target::Rust);
+
+        let dir = std::env::temp_dir();
+        let src_path = dir.join("brainfuck_transpile_test_wide_add.rs");
+        let bin_path = dir.join("brainfuck_transpile_test_wide_add_bin");
+        std::fs::write(&src_path, &source).unwrap();
+
+        let compile = Command::new("rustc")
+            .args(["-o", bin_path.to_str().unwrap(), src_path.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert!(
+            compile.status.success(),
+            "generated Rust failed to compile: {}",
+            String::from_utf8_lossy(&compile.stderr)
+        );
+
+        let run = Command::new(&bin_path).output().unwrap();
+        assert_eq!(run.stdout, vec![200u8]);
+
+        let _ = std::fs::remove_file(&src_path);
+        let _ = std::fs::remove_file(&bin_path);
+    }
+
+    #[test]
+    fn test_rust_folds_add_move_set_zero() {
+        let result = transpile(&[Add(3), Move(2), SetZero], Target::Rust);
+        assert!(result.contains("mem[p] = mem[p].wrapping_add(3i32 as u8);"));
+        assert!(result.contains("mv(&mut mem, &mut p, 2);"));
+        assert!(result.contains("mem[p] = 0;"));
+    }
+
+    #[test]
+    fn test_c_folds_add_move_set_zero() {
+        let result = transpile(&[Add(3), Move(2), SetZero], Target::C);
+        assert!(result.contains("mem[p] += 3;"));
+        assert!(result.contains("mv(2);"));
+        assert!(result.contains("mem[p] = 0;"));
+    }
+
+    #[test]
+    fn test_rust_loop_maps_to_while() {
+        let result = transpile(&[BeginLoop(2), Decrement, EndLoop(0)], Target::Rust);
+        assert!(result.contains("while mem[p] != 0 {"));
+        assert!(result.contains("mem[p] = mem[p].wrapping_sub(1);"));
+    }
+
+    #[test]
+    fn test_c_loop_maps_to_while() {
+        let result = transpile(&[BeginLoop(2), Decrement, EndLoop(0)], Target::C);
+        assert!(result.contains("while (mem[p]) {"));
+        assert!(result.contains("mem[p]--;"));
+    }
+
+    #[test]
+    fn test_rust_mul_add_emits_contribution_and_offset_moves() {
+        let result = transpile(&[MulAdd(1, 3)], Target::Rust);
+        assert!(result.contains("(mem[p] as i32).wrapping_mul(3)"));
+        assert!(result.contains("mv(&mut mem, &mut p, 1);"));
+        assert!(result.contains("mv(&mut mem, &mut p, -1);"));
+    }
+
+    #[test]
+    fn test_c_mul_add_emits_contribution_and_offset_moves() {
+        let result = transpile(&[MulAdd(1, 3)], Target::C);
+        assert!(result.contains("(int)mem[p] * 3"));
+        assert!(result.contains("mv(1);"));
+        assert!(result.contains("mv(-1);"));
+    }
+
+    #[test]
+    fn test_rust_mul_add_with_negative_offset_avoids_double_negative() {
+        let result = transpile(&[MulAdd(-2, 1)], Target::Rust);
+        assert!(result.contains("mv(&mut mem, &mut p, -2);"));
+        assert!(result.contains("mv(&mut mem, &mut p, 2);"));
+        assert!(!result.contains("--2"));
+    }
+
+    #[test]
+    fn test_c_mul_add_with_negative_offset_avoids_double_negative() {
+        let result = transpile(&[MulAdd(-2, 1)], Target::C);
+        assert!(result.contains("mv(-2);"));
+        assert!(result.contains("mv(2);"));
+        assert!(!result.contains("--2"));
+    }
+
+    #[test]
+    fn test_nested_loops_indent_increases() {
+        let result = transpile(
+            &[BeginLoop(4), GoRight, BeginLoop(3), Decrement, EndLoop(2), EndLoop(0)],
+            Target::Rust,
+        );
+        let lines: Vec<&str> = result.lines().collect();
+        let inner_while = lines
+            .iter()
+            .rev()
+            .find(|line| line.trim_start() == "while mem[p] != 0 {")
+            .unwrap();
+        assert!(inner_while.starts_with("        "));
+    }
+
+    #[test]
+    fn test_io_instructions_present() {
+        let result = transpile(&[Input, Output], Target::C);
+        assert!(result.contains("getchar()"));
+        assert!(result.contains("putchar(mem[p]);"));
+    }
+}